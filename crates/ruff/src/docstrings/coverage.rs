@@ -0,0 +1,274 @@
+//! Docstring-coverage reporting, built atop the `visibility` module.
+//!
+//! Rather than emitting a diagnostic per undocumented definition (as the
+//! D-series rules do), this module tallies how many *public* definitions in
+//! a file carry a docstring, broken down by [`Modifier`], so that the result
+//! can be reported as a project-wide coverage metric (e.g. for a CI gate).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use rustpython_parser::ast::{Constant, ExprKind, Stmt, StmtKind};
+use serde::Serialize;
+
+use crate::docstrings::definition::Documentable;
+use crate::visibility::{module_visibility, transition_scope, Modifier, VisibleScope, Visibility};
+
+/// Documented vs. total counts for a single [`Modifier`] within a file.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ModifierCounts {
+    pub total: usize,
+    pub documented: usize,
+}
+
+impl ModifierCounts {
+    fn record(&mut self, documented: bool) {
+        self.total += 1;
+        if documented {
+            self.documented += 1;
+        }
+    }
+
+    /// The percentage (0-100) of definitions that are documented.
+    ///
+    /// Returns `100.0` for a modifier with no public definitions, so that an
+    /// empty file doesn't drag down an aggregate percentage.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            100.0 * self.documented as f64 / self.total as f64
+        }
+    }
+}
+
+/// Per-file docstring coverage, broken down by [`Modifier`].
+#[derive(Debug, Clone, Serialize)]
+pub struct FileCoverage {
+    pub path: PathBuf,
+    pub counts: HashMap<Modifier, ModifierCounts>,
+}
+
+impl FileCoverage {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Record the result of a single public `Documentable` definition.
+    ///
+    /// Private definitions (per the `visibility` module, and now `__all__`)
+    /// are excluded: coverage is a measure of the *public* surface.
+    pub fn record(&mut self, modifier: Modifier, documented: bool) {
+        self.counts.entry(modifier).or_default().record(documented);
+    }
+
+    /// Total public definitions and how many of them are documented.
+    pub fn totals(&self) -> ModifierCounts {
+        let mut totals = ModifierCounts::default();
+        for counts in self.counts.values() {
+            totals.total += counts.total;
+            totals.documented += counts.documented;
+        }
+        totals
+    }
+
+    pub fn percent(&self) -> f64 {
+        self.totals().percent()
+    }
+}
+
+/// Aggregate docstring coverage across every file in a project.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProjectCoverage {
+    pub files: Vec<FileCoverage>,
+}
+
+impl ProjectCoverage {
+    pub fn push(&mut self, file: FileCoverage) {
+        self.files.push(file);
+    }
+
+    /// Total public definitions and how many of them are documented,
+    /// across every file.
+    pub fn totals(&self) -> ModifierCounts {
+        let mut totals = ModifierCounts::default();
+        for file in &self.files {
+            let file_totals = file.totals();
+            totals.total += file_totals.total;
+            totals.documented += file_totals.documented;
+        }
+        totals
+    }
+
+    pub fn percent(&self) -> f64 {
+        self.totals().percent()
+    }
+
+    /// Returns `true` if the project's overall public coverage meets or
+    /// exceeds `threshold` (a percentage in `0.0..=100.0`).
+    pub fn meets_threshold(&self, threshold: f64) -> bool {
+        self.percent() >= threshold
+    }
+
+    /// Render a machine-readable JSON report.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Render a human-readable summary, one line per file plus a total.
+    pub fn to_summary(&self) -> String {
+        let mut summary = String::new();
+        for file in &self.files {
+            summary.push_str(&format!(
+                "{}: {:.1}% ({}/{})\n",
+                file.path.display(),
+                file.percent(),
+                file.totals().documented,
+                file.totals().total,
+            ));
+        }
+        let totals = self.totals();
+        summary.push_str(&format!(
+            "total: {:.1}% ({}/{})\n",
+            self.percent(),
+            totals.documented,
+            totals.total,
+        ));
+        summary
+    }
+}
+
+/// Walk a file's AST, transitioning `VisibleScope` exactly as the D-series
+/// checker does for each `Documentable` definition, and tally the result
+/// into a `FileCoverage` instead of emitting per-site diagnostics.
+pub fn collect_file_coverage(path: &Path, body: &[Stmt]) -> FileCoverage {
+    let mut file = FileCoverage::new(path);
+    if matches!(module_visibility(path), Visibility::Public) {
+        file.record(Modifier::Module, has_docstring(body));
+    }
+    let root = VisibleScope::module(path, body, false);
+    walk(body, &root, &mut file);
+    file
+}
+
+fn walk(body: &[Stmt], scope: &VisibleScope, file: &mut FileCoverage) {
+    for stmt in body {
+        match &stmt.node {
+            StmtKind::FunctionDef { body: inner, .. } | StmtKind::AsyncFunctionDef { body: inner, .. } => {
+                let child = transition_scope(scope, stmt, &Documentable::Function);
+                if matches!(child.visibility, Visibility::Public) {
+                    file.record(Modifier::Function, has_docstring(inner));
+                }
+                walk(inner, &child, file);
+            }
+            StmtKind::ClassDef { body: inner, .. } => {
+                let child = transition_scope(scope, stmt, &Documentable::Class);
+                if matches!(child.visibility, Visibility::Public) {
+                    file.record(Modifier::Class, has_docstring(inner));
+                }
+                walk(inner, &child, file);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns `true` if a definition's body opens with a string-literal
+/// expression statement, i.e. a docstring.
+fn has_docstring(body: &[Stmt]) -> bool {
+    matches!(
+        body.first().map(|stmt| &stmt.node),
+        Some(StmtKind::Expr { value })
+            if matches!(&value.node, ExprKind::Constant { value: Constant::Str(_), .. })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use rustpython_parser::parser;
+
+    use super::*;
+
+    #[test]
+    fn tallies_documented_and_undocumented_public_definitions() {
+        // A public-module path: in a private module every definition is
+        // private and nothing would be tallied. The module itself has no
+        // docstring, so it adds one undocumented entry to the totals.
+        let body = parser::parse_program(
+            "def documented():\n    \"\"\"Has a docstring.\"\"\"\n\n\ndef undocumented():\n    pass\n\n\nclass Widget:\n    \"\"\"Has a docstring.\"\"\"\n",
+            "<test>",
+        )
+        .unwrap();
+
+        let coverage = collect_file_coverage(Path::new("widgets.py"), &body);
+
+        let functions = coverage.counts[&Modifier::Function];
+        assert_eq!(functions.total, 2);
+        assert_eq!(functions.documented, 1);
+
+        let classes = coverage.counts[&Modifier::Class];
+        assert_eq!(classes.total, 1);
+        assert_eq!(classes.documented, 1);
+
+        assert_eq!(coverage.totals().total, 4);
+        assert_eq!(coverage.totals().documented, 2);
+    }
+
+    #[test]
+    fn excludes_private_definitions() {
+        let body =
+            parser::parse_program("def _helper():\n    pass\n", "<test>").unwrap();
+        let coverage = collect_file_coverage(Path::new("_internal.py"), &body);
+        assert!(coverage.counts.is_empty());
+    }
+
+    #[test]
+    fn records_module_level_coverage() {
+        let documented =
+            parser::parse_program("\"\"\"Module docstring.\"\"\"\n\nx = 1\n", "<test>").unwrap();
+        let coverage = collect_file_coverage(Path::new("documented.py"), &documented);
+        let module = coverage.counts[&Modifier::Module];
+        assert_eq!(module.total, 1);
+        assert_eq!(module.documented, 1);
+
+        let undocumented = parser::parse_program("x = 1\n", "<test>").unwrap();
+        let coverage = collect_file_coverage(Path::new("undocumented.py"), &undocumented);
+        let module = coverage.counts[&Modifier::Module];
+        assert_eq!(module.total, 1);
+        assert_eq!(module.documented, 0);
+    }
+
+    #[test]
+    fn private_module_excluded_from_module_coverage() {
+        let body = parser::parse_program("x = 1\n", "<test>").unwrap();
+        let coverage = collect_file_coverage(Path::new("_internal.py"), &body);
+        assert!(!coverage.counts.contains_key(&Modifier::Module));
+    }
+
+    #[test]
+    fn project_coverage_aggregates_across_files() {
+        // Public-module paths (a private module's definitions are all
+        // private), so each file also contributes its module-level entry:
+        // `a.py` is fully documented, `b.py` not at all.
+        let documented = parser::parse_program(
+            "\"\"\"Module docstring.\"\"\"\n\n\ndef public():\n    \"\"\"Docstring.\"\"\"\n",
+            "<test>",
+        )
+        .unwrap();
+        let undocumented = parser::parse_program("def public():\n    pass\n", "<test>").unwrap();
+
+        let mut project = ProjectCoverage::default();
+        project.push(collect_file_coverage(Path::new("a.py"), &documented));
+        project.push(collect_file_coverage(Path::new("b.py"), &undocumented));
+
+        assert_eq!(project.totals().total, 4);
+        assert_eq!(project.totals().documented, 2);
+        assert!(project.meets_threshold(50.0));
+        assert!(!project.meets_threshold(75.0));
+    }
+}
@@ -1,13 +1,16 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
+use rustpython_parser::ast::{Arg, Located, Location, Stmt, StmtKind};
 use rustpython_parser::lexer::LexResult;
-use rustpython_parser::Tok;
+use rustpython_parser::{parse_expression, Tok};
 
 use ruff_macros::{define_violation, derive_message_formats};
 
+use crate::ast::types::Range;
+use crate::fix::Fix;
 use crate::registry::Diagnostic;
-use crate::violation::Violation;
-use crate::Range;
+use crate::source_code::Locator;
+use crate::violation::{AutofixKind, Violation};
 
 define_violation!(
     /// ## What it does
@@ -31,34 +34,391 @@ define_violation!(
     pub struct TypeCommentInStub;
 );
 impl Violation for TypeCommentInStub {
+    const AUTOFIX: AutofixKind = AutofixKind::Sometimes;
+
     #[derive_message_formats]
     fn message(&self) -> String {
         format!("Don't use type comments in stub file")
     }
+
+    fn autofix_title(&self) -> Option<String> {
+        Some("Replace type comment with an annotation".to_string())
+    }
 }
 
 static TYPE_COMMENT_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^#\s*type:\s*([^#]+)(\s*#.*?)?$").unwrap());
 static TYPE_IGNORE_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^#\s*type:\s*ignore([^#]+)?(\s*#.*?)?$").unwrap());
+// A function-level signature comment, e.g. `# type: (int, str) -> bool`.
+static SIGNATURE_COMMENT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\((?P<args>.*)\)\s*->\s*(?P<returns>.+)$").unwrap());
+
+/// Returns `true` if `type_text` parses as a single, valid annotation
+/// expression (and is therefore safe to splice into the source as one).
+fn is_valid_annotation(type_text: &str) -> bool {
+    parse_expression(type_text, "<type_comment>").is_ok()
+}
+
+/// Split `text` on top-level occurrences of `,`, ignoring commas nested
+/// inside `()`, `[]`, or `{}` -- e.g. `"int, Dict[str, int]"` splits into
+/// `["int", "Dict[str, int]"]`, not four pieces.
+fn split_top_level_commas(text: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut start = 0usize;
+    for (index, ch) in text.char_indices() {
+        match ch {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(text[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    let tail = text[start..].trim();
+    if !tail.is_empty() {
+        parts.push(tail);
+    }
+    parts
+}
+
+/// Returns `true` if a signature comment's argument list is the bare
+/// ellipsis (`# type: (...) -> bool`), which only pins down the return
+/// type and leaves every parameter unannotated -- even one whose count
+/// happens to match a single-parameter function.
+fn is_ellipsis_args(arg_types: &[&str]) -> bool {
+    arg_types.len() == 1 && arg_types[0] == "..."
+}
+
+/// Find the simple assignment target (`x = 1`, not `x, y = 1, 2`) whose
+/// statement starts on `row`, to splice an annotation onto.
+fn find_assign_target<'a>(body: &'a [Stmt], row: usize) -> Option<&'a Located<rustpython_parser::ast::ExprKind>> {
+    for stmt in body {
+        if stmt.location.row() == row {
+            if let StmtKind::Assign { targets, .. } = &stmt.node {
+                if let [target] = targets.as_slice() {
+                    return Some(target);
+                }
+            }
+        }
+        if let Some(found) = find_assign_target(nested_body(stmt), row) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// Find the function whose signature a type comment on `row` documents.
+///
+/// Covers both placements that actually occur in stubs: the comment on its
+/// own line between the `def ...:` line and the first real body statement,
+/// and the dominant stub form where it trails the `def` line itself --
+/// `def f(a, b):  # type: (int, str) -> bool` or the one-line
+/// `def f(): ...  # type: (int, str) -> bool` (whose single body statement
+/// shares the `def` line's row). So `row` may equal the `def` line's row or
+/// fall anywhere through the first body statement's row. Nested functions
+/// are checked first, so an inner `def`'s own signature comment isn't
+/// misattributed to an enclosing one.
+fn find_enclosing_function<'a>(body: &'a [Stmt], row: usize) -> Option<&'a Stmt> {
+    for stmt in body {
+        if let Some(found) = find_enclosing_function(nested_body(stmt), row) {
+            return Some(found);
+        }
+        if let StmtKind::FunctionDef { body: fn_body, .. } | StmtKind::AsyncFunctionDef { body: fn_body, .. } =
+            &stmt.node
+        {
+            if let Some(first) = fn_body.first() {
+                if row >= stmt.location.row() && row <= first.location.row() {
+                    return Some(stmt);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn nested_body(stmt: &Stmt) -> &[Stmt] {
+    match &stmt.node {
+        StmtKind::FunctionDef { body, .. }
+        | StmtKind::AsyncFunctionDef { body, .. }
+        | StmtKind::ClassDef { body, .. }
+        | StmtKind::If { body, .. }
+        | StmtKind::With { body, .. }
+        | StmtKind::AsyncWith { body, .. } => body,
+        _ => &[],
+    }
+}
+
+/// Build the fix for a simple-assignment type comment: `x = 1  # type: int`
+/// becomes `x: int = 1`.
+fn assign_fix(
+    body: &[Stmt],
+    comment_range: Range,
+    type_text: &str,
+    trailing_comment: Option<&str>,
+) -> Option<Fix> {
+    if !is_valid_annotation(type_text) {
+        return None;
+    }
+    let target = find_assign_target(body, comment_range.location.row())?;
+
+    let mut edits = vec![Fix::insertion(
+        format!(": {type_text}"),
+        target.end_location,
+    )];
+    edits.push(match trailing_comment {
+        Some(comment) => Fix::replacement(
+            comment.trim().to_string(),
+            comment_range.location,
+            comment_range.end_location,
+        ),
+        None => Fix::deletion(comment_range.location, comment_range.end_location),
+    });
+    Some(Fix::from_iter(edits))
+}
+
+/// Build the fix for a function-signature type comment:
+/// `# type: (int, str) -> bool` distributes `int`/`str` onto the
+/// parameters and `bool` onto the return annotation.
+fn signature_fix(
+    body: &[Stmt],
+    locator: &Locator,
+    comment_range: Range,
+    args_text: &str,
+    returns_text: &str,
+    trailing_comment: Option<&str>,
+) -> Option<Fix> {
+    let function = find_enclosing_function(body, comment_range.location.row())?;
+    let (args, returns) = match &function.node {
+        StmtKind::FunctionDef { args, returns, .. }
+        | StmtKind::AsyncFunctionDef { args, returns, .. } => (args, returns),
+        _ => return None,
+    };
+    if returns.is_some() {
+        return None;
+    }
+
+    // `self`/`cls` aren't included in a type comment's argument list.
+    let annotatable: Vec<&Arg> = args
+        .args
+        .iter()
+        .enumerate()
+        .filter(|(index, arg)| !(*index == 0 && matches!(arg.node.arg.as_str(), "self" | "cls")))
+        .map(|(_, arg)| arg)
+        .filter(|arg| arg.node.annotation.is_none())
+        .collect();
+
+    let arg_types = split_top_level_commas(args_text);
+    let is_ellipsis = is_ellipsis_args(&arg_types);
+    if is_ellipsis {
+        // `(...) -> bool` only pins down the return type; leave the
+        // parameters unannotated even if their count happens to match.
+    } else if arg_types.len() != annotatable.len() {
+        // Can't safely line up the comment's positional types with the
+        // parameter list (e.g. it was written for a different signature).
+        return None;
+    } else if !arg_types.iter().all(|ty| is_valid_annotation(ty)) {
+        return None;
+    }
+
+    if !is_valid_annotation(returns_text) {
+        return None;
+    }
+
+    let mut edits = vec![];
+    if !is_ellipsis {
+        for (arg, ty) in annotatable.iter().zip(arg_types.iter()) {
+            edits.push(Fix::insertion(format!(": {ty}"), arg.end_location));
+        }
+    }
+
+    // Scan from the `def` line for the parameter list's own closing paren
+    // (by bracket depth, not `rfind`), since the header text also contains
+    // the type comment itself and may contain other parens -- from default
+    // values like `def f(a=foo(1)):` to, after this point, the comment's
+    // own `(...)`.
+    let header_range = Range::new(function.location, function_body_start(function));
+    let header_text = locator.slice(header_range);
+    let paren_offset = find_signature_close_paren(header_text)?;
+    let insert_at = offset_to_location(function.location, header_text, paren_offset + 1);
+    edits.push(Fix::insertion(format!(" -> {returns_text}"), insert_at));
+
+    edits.push(match trailing_comment {
+        Some(comment) => Fix::replacement(
+            comment.trim().to_string(),
+            comment_range.location,
+            comment_range.end_location,
+        ),
+        None => Fix::deletion(comment_range.location, comment_range.end_location),
+    });
+    Some(Fix::from_iter(edits))
+}
+
+/// Find the byte offset of the closing paren matching the *first* opening
+/// paren in `text` (i.e. the parameter list's own closing paren), tracking
+/// nesting depth so parens inside default-argument calls or annotations
+/// don't trigger a premature match.
+fn find_signature_close_paren(text: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    for (index, ch) in text.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn function_body_start(function: &Stmt) -> Location {
+    match &function.node {
+        StmtKind::FunctionDef { body, .. } | StmtKind::AsyncFunctionDef { body, .. } => body
+            .first()
+            .map_or(function.end_location.unwrap_or(function.location), |stmt| stmt.location),
+        _ => function.location,
+    }
+}
+
+fn offset_to_location(base: Location, text: &str, offset: usize) -> Location {
+    let prefix = &text[..offset];
+    match prefix.rfind('\n') {
+        None => Location::new(base.row(), base.column() + prefix.chars().count()),
+        Some(index) => Location::new(
+            base.row() + prefix.matches('\n').count(),
+            prefix[index + 1..].chars().count(),
+        ),
+    }
+}
 
 /// PYI033
-pub fn type_comment_in_stub(tokens: &[LexResult]) -> Vec<Diagnostic> {
+pub fn type_comment_in_stub(tokens: &[LexResult], locator: &Locator, body: &[Stmt]) -> Vec<Diagnostic> {
     let mut diagnostics = vec![];
 
     for token in tokens.iter().flatten() {
         if let (location, Tok::Comment(comment), end_location) = token {
-            if TYPE_COMMENT_REGEX.is_match(comment) && !TYPE_IGNORE_REGEX.is_match(comment) {
-                diagnostics.push(Diagnostic::new(
-                    TypeCommentInStub,
-                    Range {
-                        location: *location,
-                        end_location: *end_location,
-                    },
-                ));
+            let Some(captures) = TYPE_COMMENT_REGEX.captures(comment) else {
+                continue;
+            };
+            if TYPE_IGNORE_REGEX.is_match(comment) {
+                continue;
+            }
+
+            let comment_range = Range {
+                location: *location,
+                end_location: *end_location,
+            };
+            let type_text = captures.get(1).unwrap().as_str().trim();
+            let trailing_comment = captures.get(2).map(|m| m.as_str());
+
+            let mut diagnostic = Diagnostic::new(TypeCommentInStub, comment_range);
+
+            let fix = if let Some(signature) = SIGNATURE_COMMENT_REGEX.captures(type_text) {
+                signature_fix(
+                    body,
+                    locator,
+                    comment_range,
+                    signature.name("args").unwrap().as_str(),
+                    signature.name("returns").unwrap().as_str(),
+                    trailing_comment,
+                )
+            } else {
+                assign_fix(body, comment_range, type_text, trailing_comment)
+            };
+            if let Some(fix) = fix {
+                diagnostic.amend(fix);
             }
+
+            diagnostics.push(diagnostic);
         }
     }
 
     diagnostics
 }
+
+#[cfg(test)]
+mod tests {
+    use rustpython_parser::parser;
+
+    use super::*;
+
+    #[test]
+    fn splits_on_top_level_commas_only() {
+        assert_eq!(
+            split_top_level_commas("int, Dict[str, int]"),
+            vec!["int", "Dict[str, int]"]
+        );
+        assert_eq!(split_top_level_commas("..."), vec!["..."]);
+        assert_eq!(split_top_level_commas(""), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn finds_signature_close_paren_ignoring_nested_parens() {
+        let text = "def f(a=foo(1), b=(2, 3)):";
+        let offset = find_signature_close_paren(text).unwrap();
+        assert_eq!(&text[offset..=offset], ")");
+        assert_eq!(&text[..offset], "def f(a=foo(1), b=(2, 3)");
+    }
+
+    #[test]
+    fn finds_function_for_comment_on_line_before_first_body_stmt() {
+        let body = parser::parse_program(
+            "def f(a, b):\n    # type: (int, str) -> bool\n    return True\n",
+            "<test>",
+        )
+        .unwrap();
+        let function = find_enclosing_function(&body, 2).unwrap();
+        assert!(matches!(&function.node, StmtKind::FunctionDef { name, .. } if name == "f"));
+    }
+
+    #[test]
+    fn finds_function_for_comment_trailing_the_def_line() {
+        // `def f(a, b):  # type: (int, str) -> bool` -- the dominant stub
+        // placement, where the comment shares the `def` line's row.
+        let body = parser::parse_program("def f(a, b):\n    return True\n", "<test>").unwrap();
+        let function = find_enclosing_function(&body, 1).unwrap();
+        assert!(matches!(&function.node, StmtKind::FunctionDef { name, .. } if name == "f"));
+    }
+
+    #[test]
+    fn finds_function_for_one_line_stub_form() {
+        // `def f(): ...  # type: (int) -> bool` -- body and comment share
+        // the `def` line's row.
+        let body = parser::parse_program("def f(a): ...\n", "<test>").unwrap();
+        let function = find_enclosing_function(&body, 1).unwrap();
+        assert!(matches!(&function.node, StmtKind::FunctionDef { name, .. } if name == "f"));
+    }
+
+    #[test]
+    fn prefers_innermost_function_for_nested_defs() {
+        let body = parser::parse_program(
+            "def outer():\n    def inner(a):\n        # type: (int) -> bool\n        return True\n    return inner\n",
+            "<test>",
+        )
+        .unwrap();
+        let function = find_enclosing_function(&body, 3).unwrap();
+        assert!(matches!(&function.node, StmtKind::FunctionDef { name, .. } if name == "inner"));
+    }
+
+    #[test]
+    fn ellipsis_args_detected_even_with_one_parameter() {
+        // A single-parameter function's arg count (1) coincidentally
+        // matches `["..."]`'s length (1); the ellipsis form must still win.
+        assert!(is_ellipsis_args(&split_top_level_commas("...")));
+        assert!(!is_ellipsis_args(&split_top_level_commas("int")));
+        assert!(!is_ellipsis_args(&split_top_level_commas("int, str")));
+    }
+
+    #[test]
+    fn rejects_invalid_annotation_text() {
+        assert!(!is_valid_annotation("not a valid ) expr"));
+        assert!(is_valid_annotation("Dict[str, int]"));
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+
+use rustpython_parser::ast::{Expr, ExprKind};
+
+use ruff_macros::{derive_message_formats, violation};
+
+use crate::ast::context::Context;
+use crate::ast::types::Range;
+use crate::registry::Diagnostic;
+use crate::violation::Violation;
+use crate::visibility::is_magic;
+
+/// ## What it does
+/// Checks for accesses, from outside the defining module, of names that
+/// module marks private via a leading underscore.
+///
+/// ## Why is this bad?
+/// A single leading underscore (e.g. `_helper`, `obj._cache`) is a
+/// convention for "implementation detail, not part of the public API."
+/// Reaching into another module's private surface couples your code to
+/// something the owning module is free to rename or remove without
+/// warning, since (per the same convention) it never promised stability.
+///
+/// Accesses from within the defining module, or from within the same
+/// class, are allowed, as is anything this file itself re-exports via
+/// `__all__`. Dunder names (`__init__`, `__len__`, ...) are never
+/// considered private.
+///
+/// ## Example
+/// ```python
+/// import _internal
+///
+/// _internal._helper()
+/// ```
+///
+/// Use instead:
+/// ```python
+/// import _internal
+///
+/// _internal.helper()
+/// ```
+#[violation]
+pub struct PrivateMemberAccess {
+    pub access: String,
+}
+
+impl Violation for PrivateMemberAccess {
+    #[derive_message_formats]
+    fn message(&self) -> String {
+        let PrivateMemberAccess { access } = self;
+        format!("Private member accessed: `{access}`")
+    }
+}
+
+/// Returns `true` if `name` is considered private by convention (a single
+/// leading underscore, but not a dunder name).
+fn is_private_name(name: &str) -> bool {
+    name.starts_with('_') && !is_magic(name)
+}
+
+/// Shared logic for both entry points below: given the access's private
+/// `name` and the `call_path` it resolves to, decide whether it's a
+/// cross-module private access worth flagging.
+fn check(
+    ctx: &Context,
+    expr: &Expr,
+    name: &str,
+    call_path: &crate::ast::types::CallPath,
+    exports: Option<&HashSet<String>>,
+) -> Option<Diagnostic> {
+    if !is_private_name(name) {
+        return None;
+    }
+
+    if exports.map_or(false, |exports| exports.contains(name)) {
+        return None;
+    }
+
+    // Same-module access is fine; only flag reaches into a *different*
+    // module's private surface.
+    if ctx
+        .module_path
+        .as_ref()
+        .map_or(false, |module_path| call_path.as_slice().starts_with(module_path))
+    {
+        return None;
+    }
+
+    Some(Diagnostic::new(
+        PrivateMemberAccess {
+            access: name.to_string(),
+        },
+        Range::from_located(expr),
+    ))
+}
+
+/// SLF001
+///
+/// `exports` is the current file's own `__all__` (see `visibility`): a name
+/// that this file deliberately re-exports is treated as public, since the
+/// file has taken on responsibility for its stability.
+///
+/// Handles both attribute access (`pkg.mod._private`, `obj._attr`) and a
+/// bare reference to a name imported directly from another module
+/// (`from pkg.mod import _private; _private()`).
+pub fn private_member_access(
+    ctx: &Context,
+    expr: &Expr,
+    exports: Option<&HashSet<String>>,
+) -> Option<Diagnostic> {
+    match &expr.node {
+        ExprKind::Attribute { value, attr, .. } => {
+            if !is_private_name(attr) {
+                return None;
+            }
+
+            // `self._attr` and `cls._attr` are always fine: that's
+            // same-class access, not a cross-module reach.
+            if matches!(&value.node, ExprKind::Name { id, .. } if id == "self" || id == "cls") {
+                return None;
+            }
+
+            // Resolve the accessed object back to the module that defines
+            // it. If it doesn't resolve to an import (e.g. it's a local
+            // variable), there's nothing cross-module to flag.
+            let call_path = ctx.resolve_call_path(value)?;
+            check(ctx, expr, attr, &call_path, exports)
+        }
+        ExprKind::Name { id, .. } => {
+            if !is_private_name(id) {
+                return None;
+            }
+
+            // Resolve the name itself: if it traces back to
+            // `from <module> import _private`, `call_path` ends in
+            // `_private` and is prefixed by the defining module.
+            let call_path = ctx.resolve_call_path(expr)?;
+            check(ctx, expr, id, &call_path, exports)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_private_name;
+
+    #[test]
+    fn single_underscore_is_private() {
+        assert!(is_private_name("_helper"));
+        assert!(is_private_name("_cache"));
+    }
+
+    #[test]
+    fn public_names_are_not_private() {
+        assert!(!is_private_name("helper"));
+        assert!(!is_private_name("PublicClass"));
+    }
+
+    #[test]
+    fn dunder_names_are_not_private() {
+        assert!(!is_private_name("__init__"));
+        assert!(!is_private_name("__len__"));
+    }
+
+    // `private_member_access` itself resolves accesses via `Context`, whose
+    // import/binding machinery is exercised through this crate's
+    // fixture-based integration tests (see the other flake8-self rule
+    // fixtures) rather than a unit test here.
+}
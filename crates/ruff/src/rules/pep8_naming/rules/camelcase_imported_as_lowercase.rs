@@ -1,13 +1,16 @@
-use rustpython_parser::ast::Stmt;
+use rustpython_parser::ast::{Location, Stmt};
 
 use ruff_macros::{derive_message_formats, violation};
 use ruff_python_stdlib::str;
 
+use crate::ast::context::Context;
 use crate::ast::helpers::identifier_range;
+use crate::ast::types::Range;
+use crate::fix::Fix;
 use crate::registry::Diagnostic;
 use crate::rules::pep8_naming::helpers;
 use crate::source_code::Locator;
-use crate::violation::Violation;
+use crate::violation::{AutofixKind, Violation};
 
 /// ## What it does
 /// Checks for `CamelCase` imports that are aliased to lowercase names.
@@ -39,28 +42,134 @@ pub struct CamelcaseImportedAsLowercase {
 }
 
 impl Violation for CamelcaseImportedAsLowercase {
+    const AUTOFIX: AutofixKind = AutofixKind::Sometimes;
+
     #[derive_message_formats]
     fn message(&self) -> String {
         let CamelcaseImportedAsLowercase { name, asname } = self;
         format!("Camelcase `{name}` imported as lowercase `{asname}`")
     }
+
+    fn autofix_title(&self) -> Option<String> {
+        let CamelcaseImportedAsLowercase { name, asname } = self;
+        Some(format!("Drop alias `{asname}` and use `{name}` directly"))
+    }
+}
+
+/// Translate a byte offset into `text` into a `Location`, relative to
+/// `base` (the `Location` at which `text` itself starts).
+fn relative_location(base: Location, text: &str, offset: usize) -> Location {
+    let prefix = &text[..offset];
+    match prefix.rfind('\n') {
+        None => Location::new(base.row(), base.column() + prefix.chars().count()),
+        Some(index) => Location::new(
+            base.row() + prefix.matches('\n').count(),
+            prefix[index + 1..].chars().count(),
+        ),
+    }
+}
+
+/// Attempt to build a fix that drops the now-redundant `as asname` suffix
+/// from the import and renames every in-scope reference to `asname` to
+/// `name`, like an IDE rename refactor.
+///
+/// Bails out (returning `None`) if `name` is already bound in scope (the
+/// rename would collide), or if any reference to `asname` lives in a
+/// nested scope we can't prove is safe to rewrite.
+fn rename_fix(ctx: &Context, import_from: &Stmt, name: &str, asname: &str, locator: &Locator) -> Option<Fix> {
+    let scope = ctx.scope();
+    if scope.bindings.contains_key(name) {
+        return None;
+    }
+
+    let binding = scope.bindings.get(asname).map(|id| &ctx.bindings[*id])?;
+
+    // Each entry in `binding.references` is a `ReferenceId` into the
+    // usage table (`ctx.references`), not another binding -- a binding's
+    // own `range` is where `asname` is *defined*, not where it's used.
+    let references: Vec<_> = binding
+        .references
+        .iter()
+        .map(|reference_id| &ctx.references[*reference_id])
+        .collect();
+    if references
+        .iter()
+        .any(|reference| reference.scope_id != binding.scope_id)
+    {
+        return None;
+    }
+
+    let import_range = Range::from_located(import_from);
+    let import_text = locator.slice(import_range);
+    let suffix = format!(" as {asname}");
+    let offset = import_text.find(&suffix)?;
+
+    let mut edits = Vec::with_capacity(references.len() + 1);
+    edits.push(Fix::deletion(
+        relative_location(import_range.location, import_text, offset),
+        relative_location(import_range.location, import_text, offset + suffix.len()),
+    ));
+    for reference in references {
+        edits.push(Fix::replacement(
+            name.to_string(),
+            reference.range.location,
+            reference.range.end_location,
+        ));
+    }
+
+    Some(Fix::from_iter(edits))
 }
 
 /// N813
 pub fn camelcase_imported_as_lowercase(
+    ctx: &Context,
     import_from: &Stmt,
     name: &str,
     asname: &str,
     locator: &Locator,
 ) -> Option<Diagnostic> {
     if helpers::is_camelcase(name) && str::is_lower(asname) {
-        return Some(Diagnostic::new(
+        let mut diagnostic = Diagnostic::new(
             CamelcaseImportedAsLowercase {
                 name: name.to_string(),
                 asname: asname.to_string(),
             },
             identifier_range(import_from, locator),
-        ));
+        );
+        if let Some(fix) = rename_fix(ctx, import_from, name, asname, locator) {
+            diagnostic.amend(fix);
+        }
+        return Some(diagnostic);
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use rustpython_parser::ast::Location;
+
+    use super::relative_location;
+
+    #[test]
+    fn offset_on_first_line() {
+        let base = Location::new(1, 0);
+        let text = "from example import MyClassName as myclassname";
+        let offset = text.find(" as myclassname").unwrap();
+        assert_eq!(relative_location(base, text, offset), Location::new(1, 31));
+    }
+
+    #[test]
+    fn offset_after_newline() {
+        let base = Location::new(5, 4);
+        let text = "from example import (\n    MyClassName as myclassname,\n)";
+        let offset = text.find(" as myclassname").unwrap();
+        // Row advances by the number of embedded newlines before `offset`,
+        // and the column resets relative to the start of that line.
+        assert_eq!(relative_location(base, text, offset), Location::new(6, 15));
+    }
+
+    // `rename_fix`'s binding/reference resolution is exercised through this
+    // crate's fixture-based integration tests for N813 (see the other
+    // pep8-naming rule fixtures), since it depends on `Context`'s full
+    // binding/scope model rather than data constructible in a unit test.
+}
@@ -1,16 +1,19 @@
 //! Abstractions for tracking public and private visibility across modules,
 //! classes, and functions.
 
+use std::collections::HashSet;
 use std::path::Path;
+use std::rc::Rc;
 
-use rustpython_parser::ast::{Expr, Stmt, StmtKind};
+use rustpython_parser::ast::{Constant, Expr, ExprKind, Stmt, StmtKind};
+use serde::Serialize;
 
 use crate::ast::context::Context;
 use crate::ast::helpers::{collect_call_path, map_callable};
 use crate::ast::types::CallPath;
 use crate::docstrings::definition::Documentable;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum Modifier {
     Module,
     Class,
@@ -23,10 +26,50 @@ pub enum Visibility {
     Private,
 }
 
+/// The `__all__` names declared by a module, plus whether a top-level name
+/// that's absent from that list should be treated as non-public.
+///
+/// The latter is opt-in (see `VisibleScope::module`): by default, an
+/// unlisted name still falls back to underscore-prefix naming, since many
+/// codebases declare a partial `__all__` without intending it to demote
+/// every other top-level name to private.
+#[derive(Debug, Clone)]
+pub struct Exports {
+    pub names: HashSet<String>,
+    pub omitted_is_private: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct VisibleScope {
     pub modifier: Modifier,
     pub visibility: Visibility,
+    /// The enclosing module's `__all__`, if any. When present, a listed
+    /// name takes precedence over underscore-prefix naming.
+    pub exports: Option<Rc<Exports>>,
+}
+
+impl VisibleScope {
+    /// Construct the root (module-level) `VisibleScope` for a file, honoring
+    /// any `__all__` declaration found in `body`.
+    ///
+    /// `omitted_is_private` controls whether a top-level name that's absent
+    /// from a present `__all__` is treated as private; pass `false` to only
+    /// use `__all__` to promote underscore-prefixed names to public.
+    pub fn module(path: &Path, body: &[Stmt], omitted_is_private: bool) -> Self {
+        let names = extract_all_names(body);
+        Self {
+            modifier: Modifier::Module,
+            visibility: module_visibility(path),
+            exports: if names.is_empty() {
+                None
+            } else {
+                Some(Rc::new(Exports {
+                    names,
+                    omitted_is_private,
+                }))
+            },
+        }
+    }
 }
 
 /// Returns `true` if a function is a "static method".
@@ -133,6 +176,67 @@ fn stem(path: &str) -> &str {
     }
 }
 
+/// Returns `true` if `expr` refers to the module-level `__all__` binding.
+fn is_dunder_all(expr: &Expr) -> bool {
+    matches!(&expr.node, ExprKind::Name { id, .. } if id == "__all__")
+}
+
+/// Recursively collect string literals out of a list, tuple, or set literal
+/// (the shapes that `__all__` is conventionally assigned from).
+fn collect_string_literals(expr: &Expr, names: &mut HashSet<String>) {
+    match &expr.node {
+        ExprKind::List { elts, .. } | ExprKind::Tuple { elts, .. } | ExprKind::Set { elts, .. } => {
+            for elt in elts {
+                collect_string_literals(elt, names);
+            }
+        }
+        ExprKind::Constant {
+            value: Constant::Str(value),
+            ..
+        } => {
+            names.insert(value.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Collect the names declared via `__all__ = [...]`, `__all__ += [...]`,
+/// `__all__.extend([...])`, and `__all__.append(...)` at module scope.
+fn extract_all_names(body: &[Stmt]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for stmt in body {
+        match &stmt.node {
+            StmtKind::Assign { targets, value, .. } => {
+                if targets.iter().any(is_dunder_all) {
+                    collect_string_literals(value, &mut names);
+                }
+            }
+            StmtKind::AugAssign { target, value, .. } => {
+                if is_dunder_all(target) {
+                    collect_string_literals(value, &mut names);
+                }
+            }
+            StmtKind::Expr { value } => {
+                if let ExprKind::Call { func, args, .. } = &value.node {
+                    if let ExprKind::Attribute { value: object, attr, .. } = &func.node {
+                        if is_dunder_all(object) && attr == "extend" {
+                            for arg in args {
+                                collect_string_literals(arg, &mut names);
+                            }
+                        } else if is_dunder_all(object) && attr == "append" {
+                            for arg in args {
+                                collect_string_literals(arg, &mut names);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
 /// Return the `Visibility` of the Python file at `Path` based on its name.
 pub fn module_visibility(path: &Path) -> Visibility {
     let mut components = path.iter().rev();
@@ -159,9 +263,23 @@ pub fn module_visibility(path: &Path) -> Visibility {
     Visibility::Public
 }
 
-fn function_visibility(stmt: &Stmt) -> Visibility {
+/// Determine the `Visibility` of a module-level function definition.
+///
+/// If the enclosing module declares `__all__`, a listed name is public even
+/// if it starts with `_`. An unlisted top-level name only falls back to
+/// underscore-prefix naming unless `Exports::omitted_is_private` opts into
+/// treating the omission itself as private.
+fn function_visibility(stmt: &Stmt, exports: Option<&Exports>) -> Visibility {
     match &stmt.node {
         StmtKind::FunctionDef { name, .. } | StmtKind::AsyncFunctionDef { name, .. } => {
+            if let Some(exports) = exports {
+                if exports.names.contains(name) {
+                    return Visibility::Public;
+                }
+                if exports.omitted_is_private {
+                    return Visibility::Private;
+                }
+            }
             if name.starts_with('_') {
                 Visibility::Private
             } else {
@@ -209,9 +327,19 @@ fn method_visibility(stmt: &Stmt) -> Visibility {
     }
 }
 
-fn class_visibility(stmt: &Stmt) -> Visibility {
+/// Determine the `Visibility` of a module-level class definition, honoring
+/// `__all__` the same way as [`function_visibility`].
+fn class_visibility(stmt: &Stmt, exports: Option<&Exports>) -> Visibility {
     match &stmt.node {
         StmtKind::ClassDef { name, .. } => {
+            if let Some(exports) = exports {
+                if exports.names.contains(name) {
+                    return Visibility::Public;
+                }
+                if exports.omitted_is_private {
+                    return Visibility::Private;
+                }
+            }
             if name.starts_with('_') {
                 Visibility::Private
             } else {
@@ -234,13 +362,16 @@ pub fn transition_scope(scope: &VisibleScope, stmt: &Stmt, kind: &Documentable)
                 VisibleScope {
                     modifier: Modifier::Module,
                     visibility: Visibility::Public,
-                } => function_visibility(stmt),
+                    exports,
+                } => function_visibility(stmt, exports.as_deref()),
                 VisibleScope {
                     modifier: Modifier::Class,
                     visibility: Visibility::Public,
+                    ..
                 } => method_visibility(stmt),
                 _ => Visibility::Private,
             },
+            exports: scope.exports.clone(),
         },
         Documentable::Class => VisibleScope {
             modifier: Modifier::Class,
@@ -248,13 +379,89 @@ pub fn transition_scope(scope: &VisibleScope, stmt: &Stmt, kind: &Documentable)
                 VisibleScope {
                     modifier: Modifier::Module,
                     visibility: Visibility::Public,
-                } => class_visibility(stmt),
+                    exports,
+                } => class_visibility(stmt, exports.as_deref()),
                 VisibleScope {
                     modifier: Modifier::Class,
                     visibility: Visibility::Public,
-                } => class_visibility(stmt),
+                    ..
+                } => class_visibility(stmt, None),
                 _ => Visibility::Private,
             },
+            exports: scope.exports.clone(),
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rustpython_parser::parser;
+
+    use super::*;
+
+    fn function_named<'a>(body: &'a [Stmt], name: &str) -> &'a Stmt {
+        body.iter()
+            .find(|stmt| matches!(&stmt.node, StmtKind::FunctionDef { name: n, .. } if n == name))
+            .expect("no such function in body")
+    }
+
+    #[test]
+    fn all_promotes_underscore_name_to_public() {
+        let body = parser::parse_program("__all__ = [\"_helper\"]\ndef _helper(): ...\n", "<test>")
+            .unwrap();
+        let exports = extract_all_names(&body);
+        let exports = Exports {
+            names: exports,
+            omitted_is_private: false,
+        };
+        assert!(matches!(
+            function_visibility(function_named(&body, "_helper"), Some(&exports)),
+            Visibility::Public
+        ));
+    }
+
+    #[test]
+    fn omitted_name_falls_back_to_naming_by_default() {
+        let body =
+            parser::parse_program("__all__ = [\"public\"]\ndef other(): ...\n", "<test>").unwrap();
+        let exports = Exports {
+            names: extract_all_names(&body),
+            omitted_is_private: false,
+        };
+        // Not listed in `__all__`, but `omitted_is_private` is off, so the
+        // underscore-naming rule still applies: `other` has no leading
+        // underscore, so it stays public.
+        assert!(matches!(
+            function_visibility(function_named(&body, "other"), Some(&exports)),
+            Visibility::Public
+        ));
+    }
+
+    #[test]
+    fn omitted_name_is_private_when_opted_in() {
+        let body =
+            parser::parse_program("__all__ = [\"public\"]\ndef other(): ...\n", "<test>").unwrap();
+        let exports = Exports {
+            names: extract_all_names(&body),
+            omitted_is_private: true,
+        };
+        assert!(matches!(
+            function_visibility(function_named(&body, "other"), Some(&exports)),
+            Visibility::Private
+        ));
+    }
+
+    #[test]
+    fn extract_all_names_handles_extend_and_append() {
+        let body = parser::parse_program(
+            "__all__ = [\"a\"]\n__all__.extend([\"b\", \"c\"])\n__all__.append(\"d\")\n",
+            "<test>",
+        )
+        .unwrap();
+        let names = extract_all_names(&body);
+        assert_eq!(
+            names,
+            HashSet::from(["a".to_string(), "b".to_string(), "c".to_string(), "d".to_string()])
+        );
+    }
+}